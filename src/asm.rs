@@ -0,0 +1,110 @@
+//! `asm!` and `global_asm!` inline assembly.
+//!
+//! The generic string lexer would otherwise swallow an asm template string
+//! as one opaque [`TokenKind::StringLiteral`], hiding the `{0}`/`{name}`/
+//! `{:e}` placeholders that are the whole point of the template. This
+//! module is entered once the main lexer recognizes an `asm!`/`global_asm!`
+//! head and stays active for every nested delimiter inside the macro's
+//! argument list (see [`crate::lexer::Lexer::asm_stack`]), so it can:
+//!
+//! - split each template string into plain-text and placeholder spans, and
+//! - classify the operand-direction keywords (`in`, `out`, ...) and the
+//!   `options`/`clobber_abi` flag introducers instead of leaving them as
+//!   plain identifiers.
+
+use crate::lexer::Lexer;
+use crate::token::TokenKind;
+
+pub(crate) const OPERAND_KEYWORDS: &[&str] = &[
+    "in",
+    "out",
+    "inout",
+    "lateout",
+    "const",
+    "sym",
+    "options",
+    "clobber_abi",
+];
+
+/// Consumes the `asm`/`global_asm` name, the `!`, and the opening delimiter,
+/// then marks the opened frame as an active asm argument list.
+pub(crate) fn lex_asm_head(lexer: &mut Lexer, start: usize, end: usize) {
+    lexer.push(TokenKind::MacroName, start, end);
+    lexer.skip_whitespace();
+    if let Some((i, '!')) = lexer.bump() {
+        lexer.push(TokenKind::Punctuation, i, i + 1);
+    }
+    lexer.skip_whitespace();
+    if let Some((i, c @ ('(' | '[' | '{'))) = lexer.bump() {
+        lexer.push(TokenKind::Punctuation, i, i + 1);
+        let _ = c;
+        lexer.push_delim(false, true, false);
+    }
+}
+
+/// Tokenizes a `"..."` template string inside an asm argument list,
+/// emitting alternating [`TokenKind::AsmTemplateString`] and
+/// [`TokenKind::AsmPlaceholder`] spans instead of one opaque string token.
+/// `{{` and `}}` are literal braces, per the same escaping `format!` uses.
+pub(crate) fn lex_template_string(lexer: &mut Lexer, start: usize) {
+    lexer.bump(); // opening quote
+    let mut seg_start = start;
+    loop {
+        match lexer.peek_char() {
+            None => break,
+            Some('"') => {
+                let (i, _) = lexer.bump().unwrap();
+                flush_text(lexer, seg_start, i + 1);
+                return;
+            }
+            Some('\\') => {
+                lexer.bump();
+                lexer.bump();
+            }
+            Some('{') if peek_second(lexer) == Some('{') => {
+                lexer.bump();
+                lexer.bump();
+            }
+            Some('}') if peek_second(lexer) == Some('}') => {
+                lexer.bump();
+                lexer.bump();
+            }
+            Some('{') => {
+                let (i, _) = lexer.bump().unwrap();
+                flush_text(lexer, seg_start, i);
+                let placeholder_start = i;
+                let mut placeholder_end = i + 1;
+                while let Some((pi, pc)) = lexer.bump() {
+                    placeholder_end = pi + pc.len_utf8();
+                    if pc == '}' {
+                        break;
+                    }
+                }
+                lexer.push(
+                    TokenKind::AsmPlaceholder,
+                    placeholder_start,
+                    placeholder_end,
+                );
+                seg_start = placeholder_end;
+            }
+            Some(_) => {
+                lexer.bump();
+            }
+        }
+    }
+    // Unterminated string (ran off the end of the source): flush whatever
+    // text remains instead of dropping it.
+    flush_text(lexer, seg_start, lexer.src.len());
+}
+
+fn flush_text(lexer: &mut Lexer, start: usize, end: usize) {
+    if end > start {
+        lexer.push(TokenKind::AsmTemplateString, start, end);
+    }
+}
+
+fn peek_second(lexer: &Lexer) -> Option<char> {
+    let mut it = lexer.chars.clone();
+    it.next();
+    it.next().map(|(_, c)| c)
+}
@@ -0,0 +1,428 @@
+//! Doc comments (`///`, `//!`, `/** */`, `/*! */`), parsed as Markdown-lite.
+//!
+//! A doc comment's *body* is the comment with its marker stripped from each
+//! line. For a `///`/`//!` run that means one stripped line per physical
+//! source line; for a `/** */`/`/*! */` block it is the text between the
+//! opening marker and the closing `*/`, with the leading ` * ` decoration
+//! stripped from each continuation line. Either way the body is scanned
+//! for headings, inline code spans, and fenced code blocks, and fenced
+//! blocks whose info string names Rust (or names nothing) are recursively
+//! re-tokenized by calling back into [`crate::lexer::tokenize`] — this is
+//! the only place in the crate that calls the entry point recursively.
+//!
+//! Mapping body offsets back to source offsets is done through a list of
+//! [`Segment`]s, one per physical source line, each recording where that
+//! line's stripped text landed in the body string. Because segments are
+//! laid out in the body in the same order as the source, a contiguous body
+//! range is always a contiguous *slice* of the body string (handy for
+//! recursive tokenization) even though it may span several segments whose
+//! source ranges are not contiguous (a marker sits between them). Emitting
+//! a token therefore means splitting its body range at segment boundaries
+//! and emitting one source span per segment it touches.
+
+use crate::lexer::Lexer;
+use crate::token::TokenKind;
+
+const RUST_FENCE_TAGS: &[&str] = &[
+    "rust",
+    "ignore",
+    "no_run",
+    "should_panic",
+    "compile_fail",
+    "edition2015",
+    "edition2018",
+    "edition2021",
+    "edition2024",
+];
+
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    body_start: usize,
+    orig_start: usize,
+    len: usize,
+}
+
+impl Segment {
+    fn body_end(&self) -> usize {
+        self.body_start + self.len
+    }
+}
+
+/// `Some(true)` for `///`, `Some(false)` for `//!`, `None` otherwise
+/// (including the conventional non-doc `////`). Never consumes input.
+pub(crate) fn line_doc_kind(lexer: &Lexer) -> Option<bool> {
+    let mut it = lexer.chars.clone();
+    if it.next()?.1 != '/' {
+        return None;
+    }
+    if it.next()?.1 != '/' {
+        return None;
+    }
+    match it.next().map(|(_, c)| c) {
+        Some('/') => {
+            if matches!(it.next(), Some((_, '/'))) {
+                None
+            } else {
+                Some(true)
+            }
+        }
+        Some('!') => Some(false),
+        _ => None,
+    }
+}
+
+/// `Some(true)` for `/**`, `Some(false)` for `/*!`, `None` otherwise
+/// (including the conventional non-doc `/**/` and `/***`). Never consumes.
+pub(crate) fn block_doc_kind(lexer: &Lexer) -> Option<bool> {
+    let mut it = lexer.chars.clone();
+    if it.next()?.1 != '/' {
+        return None;
+    }
+    if it.next()?.1 != '*' {
+        return None;
+    }
+    match it.next().map(|(_, c)| c) {
+        Some('*') => match it.next().map(|(_, c)| c) {
+            Some('/') | Some('*') => None,
+            _ => Some(true),
+        },
+        Some('!') => Some(false),
+        _ => None,
+    }
+}
+
+/// Consumes a whole run of consecutive `///` (or `//!`) lines, building the
+/// stripped body text and its [`Segment`] map, then hands off to the
+/// Markdown-lite scanner.
+pub(crate) fn lex_doc_line_run(lexer: &mut Lexer, _first_start: usize, outer: bool) {
+    let mut body = String::new();
+    let mut segments = Vec::new();
+    // Marker tokens are pushed as each line is consumed, but the run's
+    // content (pushed afterwards, by `scan_markdown`) interleaves with them
+    // in source order. Track where this run's tokens start so they can be
+    // sorted back into source order once the whole run has been collected.
+    let run_tokens_start = lexer.tokens.len();
+
+    loop {
+        let marker_start = lexer.chars.peek().map(|&(i, _)| i).expect("marker present");
+        lexer.bump();
+        lexer.bump();
+        lexer.bump();
+        lexer.push(TokenKind::DocCommentLine, marker_start, marker_start + 3);
+
+        let content_start = lexer.chars.peek().map(|&(i, _)| i).unwrap_or(lexer.src.len());
+        let mut content_end = content_start;
+        let mut seg_end = content_start;
+        while let Some(&(i, c)) = lexer.chars.peek() {
+            if c == '\n' {
+                lexer.bump();
+                seg_end = i + 1;
+                break;
+            }
+            content_end = i + c.len_utf8();
+            seg_end = content_end;
+            lexer.bump();
+        }
+        let _ = content_end;
+        let text = &lexer.src[content_start..seg_end];
+        segments.push(Segment {
+            body_start: body.len(),
+            orig_start: content_start,
+            len: text.len(),
+        });
+        body.push_str(text);
+
+        match next_line_is_doc_marker(lexer, outer) {
+            Some(ws_end) => {
+                if let Some(&(ws_start, _)) = lexer.chars.peek() {
+                    if ws_end > ws_start {
+                        lexer.push(TokenKind::Whitespace, ws_start, ws_end);
+                        while lexer.chars.peek().map(|&(i, _)| i) != Some(ws_end) {
+                            lexer.bump();
+                        }
+                    }
+                }
+            }
+            None => break,
+        }
+    }
+
+    scan_markdown(lexer, &body, &segments);
+    lexer.tokens[run_tokens_start..].sort_by_key(|t| (t.start, t.end));
+}
+
+/// Consumes a `/** ... */` or `/*! ... */` block doc comment. Like the line
+/// form, the body is split into one [`Segment`] per physical line — a block
+/// comment is still usually written with a leading ` * ` decoration on each
+/// continuation line (stripped here, see [`strip_decoration`]), and without
+/// that stripping `scan_markdown` would see `* ```rust` instead of
+/// ` ```rust` and never recognize the fence.
+pub(crate) fn lex_doc_block(lexer: &mut Lexer, start: usize, _outer: bool) {
+    lexer.bump();
+    lexer.bump();
+    lexer.bump();
+    lexer.push(TokenKind::DocCommentLine, start, start + 3);
+
+    let content_start = lexer.chars.peek().map(|&(i, _)| i).unwrap_or(lexer.src.len());
+    let mut content_end = content_start;
+    let mut closed = false;
+    while let Some((i, c)) = lexer.bump() {
+        if c == '*' && lexer.peek_char() == Some('/') {
+            content_end = i;
+            lexer.bump();
+            closed = true;
+            break;
+        }
+    }
+    if !closed {
+        content_end = lexer.src.len();
+    }
+
+    let (body, segments) = block_segments(lexer.src, content_start, content_end);
+    scan_markdown(lexer, &body, &segments);
+
+    if closed {
+        lexer.push(TokenKind::DocCommentLine, content_end, content_end + 2);
+    }
+}
+
+/// Splits a block doc comment's interior (`src[content_start..content_end]`)
+/// into one [`Segment`] per physical line, stripping each continuation
+/// line's leading decoration. As with the line-comment run, this body is
+/// not a contiguous slice of `src` (the decoration in between lines is
+/// skipped), so it has to be assembled into an owned `String`.
+fn block_segments(src: &str, content_start: usize, content_end: usize) -> (String, Vec<Segment>) {
+    let raw = &src[content_start..content_end];
+    let mut body = String::new();
+    let mut segments = Vec::with_capacity(raw.matches('\n').count() + 1);
+    let mut line_start = content_start;
+
+    for (i, line) in raw.split_inclusive('\n').enumerate() {
+        let (text, text_start) = if i == 0 {
+            (line, line_start)
+        } else {
+            strip_decoration(line, line_start)
+        };
+        segments.push(Segment {
+            body_start: body.len(),
+            orig_start: text_start,
+            len: text.len(),
+        });
+        body.push_str(text);
+        line_start += line.len();
+    }
+
+    (body, segments)
+}
+
+/// Strips a block doc comment continuation line's leading whitespace and
+/// an optional `*` (plus one following space), the conventional decoration
+/// on lines after the first in a `/** ... */`/`/*! ... */` comment. Lines
+/// without that decoration (a block comment not written in the conventional
+/// style) are returned unchanged.
+fn strip_decoration(line: &str, line_start: usize) -> (&str, usize) {
+    let after_ws = line.trim_start_matches([' ', '\t']);
+    let Some(after_star) = after_ws.strip_prefix('*') else {
+        return (line, line_start);
+    };
+    let after_star = after_star.strip_prefix(' ').unwrap_or(after_star);
+    let start = line_start + (line.len() - after_star.len());
+    (after_star, start)
+}
+
+/// Looks ahead (without consuming, unless the caller subsequently replays
+/// the walk) for `<whitespace>*` followed by a doc marker of the same
+/// `outer`/`inner` kind, returning the byte offset just past that
+/// whitespace run if found.
+fn next_line_is_doc_marker(lexer: &Lexer, outer: bool) -> Option<usize> {
+    let mut it = lexer.chars.clone();
+    let mut ws_end = it.clone().peek().map(|&(i, _)| i)?;
+    loop {
+        match it.clone().next() {
+            Some((i, c)) if c == ' ' || c == '\t' => {
+                ws_end = i + 1;
+                it.next();
+            }
+            _ => break,
+        }
+    }
+    let mut probe = it.clone();
+    if probe.next()?.1 != '/' {
+        return None;
+    }
+    if probe.next()?.1 != '/' {
+        return None;
+    }
+    let matches = match probe.next().map(|(_, c)| c) {
+        Some('/') => outer && !matches!(probe.next(), Some((_, '/'))),
+        Some('!') => !outer,
+        _ => false,
+    };
+    matches.then_some(ws_end)
+}
+
+fn scan_markdown(lexer: &mut Lexer, body: &str, segments: &[Segment]) {
+    let mut i = 0;
+    while i < segments.len() {
+        let seg = segments[i];
+        let line = line_text(body, seg);
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+
+        if let Some(info) = fence_open_info(trimmed) {
+            push_segment(lexer, TokenKind::DocFenceMarker, seg);
+            let mut j = i + 1;
+            while j < segments.len() && !is_fence_close(line_text(body, segments[j]).trim_start()) {
+                j += 1;
+            }
+            if classify_fence_as_rust(info) {
+                highlight_nested(lexer, body, &segments[i + 1..j]);
+            } else {
+                for plain in &segments[i + 1..j] {
+                    push_segment(lexer, TokenKind::DocCommentLine, *plain);
+                }
+            }
+            if j < segments.len() {
+                push_segment(lexer, TokenKind::DocFenceMarker, segments[j]);
+                i = j + 1;
+            } else {
+                i = j;
+            }
+            continue;
+        }
+
+        if indent >= 4 && !trimmed.is_empty() {
+            let mut j = i;
+            while j < segments.len() {
+                let lt = line_text(body, segments[j]);
+                let ind = lt.len() - lt.trim_start().len();
+                if lt.trim().is_empty() || ind >= 4 {
+                    j += 1;
+                } else {
+                    break;
+                }
+            }
+            highlight_nested(lexer, body, &segments[i..j]);
+            i = j;
+            continue;
+        }
+
+        if is_heading(trimmed) {
+            push_segment(lexer, TokenKind::DocHeading, seg);
+            i += 1;
+            continue;
+        }
+
+        scan_inline_code(lexer, body, seg);
+        i += 1;
+    }
+}
+
+fn line_text(body: &str, seg: Segment) -> &str {
+    let text = &body[seg.body_start..seg.body_end()];
+    text.strip_suffix('\n').unwrap_or(text)
+}
+
+fn fence_open_info(trimmed: &str) -> Option<&str> {
+    let ticks = trimmed.chars().take_while(|&c| c == '`').count();
+    (ticks >= 3).then(|| trimmed[ticks..].trim())
+}
+
+fn is_fence_close(trimmed: &str) -> bool {
+    let ticks = trimmed.chars().take_while(|&c| c == '`').count();
+    ticks >= 3 && trimmed[ticks..].trim().is_empty()
+}
+
+fn is_heading(trimmed: &str) -> bool {
+    let hashes = trimmed.chars().take_while(|&c| c == '#').count();
+    (1..=6).contains(&hashes) && matches!(trimmed.as_bytes().get(hashes), None | Some(b' '))
+}
+
+fn classify_fence_as_rust(info: &str) -> bool {
+    let info = info.trim();
+    if info.is_empty() {
+        return true;
+    }
+    info.split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .all(|tag| RUST_FENCE_TAGS.contains(&tag))
+}
+
+/// Recursively re-tokenizes the contiguous body slice covered by `segs` as
+/// Rust, then remaps every resulting token back into the original source.
+fn highlight_nested(lexer: &mut Lexer, body: &str, segs: &[Segment]) {
+    let (Some(first), Some(last)) = (segs.first(), segs.last()) else {
+        return;
+    };
+    let lo = first.body_start;
+    let hi = last.body_end();
+    let content = &body[lo..hi];
+    for token in crate::lexer::tokenize(content) {
+        if token.kind == TokenKind::Eof {
+            continue;
+        }
+        push_mapped(lexer, segs, lo + token.start, lo + token.end, token.kind);
+    }
+}
+
+fn scan_inline_code(lexer: &mut Lexer, body: &str, seg: Segment) {
+    let line = line_text(body, seg);
+    let mut text_start = 0usize;
+    let mut idx = 0usize;
+    while let Some(rel_open) = line[idx..].find('`') {
+        let open = idx + rel_open;
+        let Some(rel_close) = line[open + 1..].find('`') else {
+            break;
+        };
+        let close = open + 1 + rel_close + 1;
+        if open > text_start {
+            push_mapped(
+                lexer,
+                &[seg],
+                seg.body_start + text_start,
+                seg.body_start + open,
+                TokenKind::DocCommentLine,
+            );
+        }
+        push_mapped(
+            lexer,
+            &[seg],
+            seg.body_start + open,
+            seg.body_start + close,
+            TokenKind::DocCodeSpan,
+        );
+        text_start = close;
+        idx = close;
+    }
+    if text_start < seg.len {
+        push_mapped(
+            lexer,
+            &[seg],
+            seg.body_start + text_start,
+            seg.body_start + seg.len,
+            TokenKind::DocCommentLine,
+        );
+    }
+}
+
+fn push_segment(lexer: &mut Lexer, kind: TokenKind, seg: Segment) {
+    push_mapped(lexer, &[seg], seg.body_start, seg.body_end(), kind);
+}
+
+/// Splits `[start, end)` (in body coordinates) at `segs` boundaries and
+/// pushes one source-mapped token per overlapping segment.
+fn push_mapped(lexer: &mut Lexer, segs: &[Segment], start: usize, end: usize, kind: TokenKind) {
+    if start >= end {
+        return;
+    }
+    for seg in segs {
+        let lo = start.max(seg.body_start);
+        let hi = end.min(seg.body_end());
+        if lo < hi {
+            let orig_lo = seg.orig_start + (lo - seg.body_start);
+            let orig_hi = seg.orig_start + (hi - seg.body_start);
+            lexer.push(kind, orig_lo, orig_hi);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! `extern` blocks and ABI strings.
+//!
+//! `extern` shows up in two unrelated positions. As a block introducer
+//! (`extern "C" { ... }`, optionally preceded by `unsafe`) it opens a scope
+//! whose `fn`/`static` items are foreign declarations: they have no body or
+//! initializer and end in `;`, because the symbol is defined elsewhere. As a
+//! function qualifier (`extern "C" fn foo() { ... }`) it instead names the
+//! calling convention of a Rust function that *does* have a body. Both
+//! spellings share `extern "ABI"`, so this module handles that shared prefix
+//! once and only pushes an [`crate::lexer::Lexer::extern_stack`] frame (via
+//! `push_delim`) when a block actually follows.
+//!
+//! The ABI string itself (`"C"`, `"system"`, ...) is tagged
+//! [`TokenKind::AbiString`] rather than left as an ordinary
+//! [`TokenKind::StringLiteral`], since its one and only job is naming a
+//! fixed calling convention, not holding arbitrary string data.
+
+use crate::lexer::Lexer;
+use crate::token::TokenKind;
+
+/// Called once the lexer has identified an `extern` keyword: pushes it,
+/// then consumes an optional ABI string and, if a block follows, the
+/// opening `{` (marking the new frame as an active extern block).
+pub(crate) fn lex_extern_head(lexer: &mut Lexer, start: usize, end: usize) {
+    lexer.push(TokenKind::ExternKeyword, start, end);
+    lexer.skip_whitespace();
+
+    if lexer.peek_char() == Some('"') {
+        lex_abi_string(lexer);
+        lexer.skip_whitespace();
+    }
+
+    if lexer.peek_char() == Some('{') {
+        let (i, _) = lexer.bump().unwrap();
+        lexer.push(TokenKind::Punctuation, i, i + 1);
+        lexer.push_delim(false, false, true);
+    }
+}
+
+/// Consumes a `"..."` ABI string and pushes it as [`TokenKind::AbiString`].
+/// Shares the escaping rules of an ordinary string, since `extern` doesn't
+/// grant its string anything special syntactically — only semantically.
+/// Also used by [`crate::lexer::Lexer`] for the ABI name arguments of a
+/// `clobber_abi(...)` asm operand, which name the same kind of thing.
+pub(crate) fn lex_abi_string(lexer: &mut Lexer) {
+    let (start, _) = lexer.bump().unwrap(); // opening quote
+    let mut end = start + 1;
+    while let Some((i, c)) = lexer.bump() {
+        end = i + c.len_utf8();
+        if c == '\\' {
+            if let Some((ei, ec)) = lexer.bump() {
+                end = ei + ec.len_utf8();
+            }
+            continue;
+        }
+        if c == '"' {
+            break;
+        }
+    }
+    lexer.push(TokenKind::AbiString, start, end);
+}
+
+/// Whether the ident the lexer is about to push names a foreign `fn` or
+/// `static` item: it does if it directly follows the `fn`/`static` keyword
+/// (or `static mut`) while inside an active extern block. Every such item is
+/// a declaration by construction (extern blocks can't contain item bodies),
+/// so no body/`;` detection is needed.
+pub(crate) fn introduces_foreign_item_name(lexer: &Lexer) -> bool {
+    match lexer.sig_hist[0] {
+        Some((TokenKind::Keyword, s, e)) => match &lexer.src[s..e] {
+            "fn" | "static" => true,
+            "mut" => matches!(
+                lexer.sig_hist[1],
+                Some((TokenKind::Keyword, s, e)) if &lexer.src[s..e] == "static"
+            ),
+            _ => false,
+        },
+        _ => false,
+    }
+}
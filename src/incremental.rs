@@ -0,0 +1,258 @@
+//! Incremental re-highlighting for editor integrations.
+//!
+//! Re-tokenizing a whole buffer on every keystroke is wasteful once files
+//! get large. This module builds an explicit [`ScopeTree`] over the output
+//! of [`crate::lexer::tokenize`] — one node per bracket-delimited region,
+//! each carrying a precomputed [`ScopeNode::depth`] — and uses it to answer
+//! "what's the smallest region that needs re-highlighting after this
+//! edit?" without re-tokenizing anything outside that region.
+//!
+//! The key operation is finding the lowest common ancestor of the scopes
+//! touched by the two ends of an edit: that ancestor is exactly the
+//! smallest scope fully containing the edit, and therefore the smallest
+//! span that must be re-lexed. With parent pointers and a precomputed
+//! depth on every node, this is the classic lockstep walk: advance
+//! whichever of the two nodes is deeper until both sit at the same depth,
+//! then advance both together until they're the same node. No visited set
+//! is needed, and each walk is bounded by the tree's depth rather than its
+//! size.
+
+use crate::lexer::tokenize;
+use crate::token::Token;
+
+#[derive(Debug, Clone)]
+pub struct ScopeNode {
+    pub start: usize,
+    pub end: usize,
+    pub depth: usize,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+}
+
+/// An arena of [`ScopeNode`]s, built bottom-up (a node is only appended once
+/// its closing delimiter is seen), so the whole-buffer root ends up last
+/// rather than at a fixed index — `root_id` records which one it is.
+#[derive(Debug, Clone)]
+pub struct ScopeTree {
+    nodes: Vec<ScopeNode>,
+    root_id: usize,
+}
+
+impl ScopeTree {
+    pub fn root(&self) -> usize {
+        self.root_id
+    }
+
+    pub fn node(&self, id: usize) -> &ScopeNode {
+        &self.nodes[id]
+    }
+
+    /// Builds the tree from a flat token list: each matched `( [ {` / `) ] }`
+    /// pair becomes a node spanning from the open delimiter's start to the
+    /// close delimiter's end, nested under whichever scope was open when
+    /// the pair started.
+    pub fn build(src: &str, tokens: &[Token]) -> Self {
+        struct OpenFrame {
+            start: usize,
+            depth: usize,
+            children: Vec<usize>,
+        }
+
+        let src_len = src.len();
+        let mut nodes = Vec::new();
+        let mut stack = vec![OpenFrame {
+            start: 0,
+            depth: 0,
+            children: Vec::new(),
+        }];
+
+        for token in tokens {
+            if token.kind != crate::token::TokenKind::Punctuation {
+                continue;
+            }
+            match token.text(src) {
+                "(" | "[" | "{" => {
+                    stack.push(OpenFrame {
+                        start: token.start,
+                        depth: stack.len(),
+                        children: Vec::new(),
+                    });
+                }
+                ")" | "]" | "}" => {
+                    if stack.len() == 1 {
+                        continue; // unmatched close; ignore rather than panic
+                    }
+                    let frame = stack.pop().unwrap();
+                    let id = nodes.len();
+                    nodes.push(ScopeNode {
+                        start: frame.start,
+                        end: token.end,
+                        depth: frame.depth,
+                        parent: None, // filled in once the parent's id is known, below
+                        children: frame.children,
+                    });
+                    stack.last_mut().unwrap().children.push(id);
+                }
+                _ => {}
+            }
+        }
+
+        // Unwind any still-open frames (unbalanced input) as if they closed
+        // at end of file, so the tree always covers the whole buffer.
+        while stack.len() > 1 {
+            let frame = stack.pop().unwrap();
+            let id = nodes.len();
+            nodes.push(ScopeNode {
+                start: frame.start,
+                end: src_len,
+                depth: frame.depth,
+                parent: None,
+                children: frame.children,
+            });
+            stack.last_mut().unwrap().children.push(id);
+        }
+
+        let root_frame = stack.pop().unwrap();
+        let root_id = nodes.len();
+        nodes.push(ScopeNode {
+            start: 0,
+            end: src_len,
+            depth: 0,
+            parent: None,
+            children: root_frame.children,
+        });
+
+        let mut tree = ScopeTree {
+            nodes,
+            root_id,
+        };
+        tree.fill_parents(root_id);
+        tree
+    }
+
+    fn fill_parents(&mut self, id: usize) {
+        let children = self.nodes[id].children.clone();
+        for child in children {
+            self.nodes[child].parent = Some(id);
+            self.fill_parents(child);
+        }
+    }
+
+    /// The deepest scope whose range contains `pos`.
+    pub fn enclosing_scope(&self, pos: usize) -> usize {
+        let mut current = self.root();
+        loop {
+            let next = self.nodes[current]
+                .children
+                .iter()
+                .copied()
+                .find(|&c| self.nodes[c].start <= pos && pos < self.nodes[c].end);
+            match next {
+                Some(child) => current = child,
+                None => return current,
+            }
+        }
+    }
+
+    /// Lowest common ancestor of `a` and `b`, via the depth-lockstep walk:
+    /// equalize depth first, then advance both parents together.
+    pub fn common_ancestor(&self, mut a: usize, mut b: usize) -> usize {
+        if a == b {
+            return a; // early-out for the common case of a single-scope edit
+        }
+        while self.nodes[a].depth > self.nodes[b].depth {
+            a = self.nodes[a].parent.expect("depth > 0 implies a parent");
+        }
+        while self.nodes[b].depth > self.nodes[a].depth {
+            b = self.nodes[b].parent.expect("depth > 0 implies a parent");
+        }
+        while a != b {
+            a = self.nodes[a].parent.expect("root is shared by all scopes");
+            b = self.nodes[b].parent.expect("root is shared by all scopes");
+        }
+        a
+    }
+}
+
+/// A half-open `[start, old_end)` replaced by new text ending at `new_end`
+/// (all three offsets are byte positions; `start`/`old_end` are in the
+/// previous source, `new_end` is in the new source).
+#[derive(Debug, Clone, Copy)]
+pub struct Edit {
+    pub start: usize,
+    pub old_end: usize,
+    pub new_end: usize,
+}
+
+/// Result of [`reparse_edit`]: the tokens covering the whole new buffer
+/// (reused spans outside the dirty region, freshly lexed spans inside it)
+/// plus the new scope tree and the `[start, end)` that was actually
+/// re-lexed, so callers can tell how much work was saved.
+pub struct Reparsed {
+    pub tokens: Vec<Token>,
+    pub tree: ScopeTree,
+    pub dirty_start: usize,
+    pub dirty_end: usize,
+}
+
+/// Re-highlights `new_src` after `edit`, re-lexing only the smallest scope
+/// that fully contains the edit (found via [`ScopeTree::common_ancestor`])
+/// instead of the whole buffer.
+pub fn reparse_edit(
+    tree: &ScopeTree,
+    old_src: &str,
+    old_tokens: &[Token],
+    new_src: &str,
+    edit: Edit,
+) -> Reparsed {
+    let scope_at_start = tree.enclosing_scope(edit.start);
+    let scope_at_old_end = tree.enclosing_scope(edit.old_end.min(old_src.len()));
+    let boundary = tree.common_ancestor(scope_at_start, scope_at_old_end);
+    let node = tree.node(boundary);
+
+    let delta = edit.new_end as isize - edit.old_end as isize;
+    let dirty_start = node.start;
+    let dirty_end = (node.end as isize + delta) as usize;
+
+    let is_real = |t: &&Token| t.kind != crate::token::TokenKind::Eof;
+
+    let mut tokens = Vec::with_capacity(old_tokens.len());
+    for token in old_tokens.iter().filter(is_real) {
+        if token.end <= dirty_start {
+            tokens.push(*token);
+        }
+    }
+
+    for fresh in tokenize(&new_src[dirty_start..dirty_end.min(new_src.len())]) {
+        if fresh.kind == crate::token::TokenKind::Eof {
+            continue;
+        }
+        tokens.push(Token::new(
+            fresh.kind,
+            fresh.start + dirty_start,
+            fresh.end + dirty_start,
+        ));
+    }
+
+    for token in old_tokens.iter().filter(is_real) {
+        if token.start >= node.end {
+            let shift = |n: usize| (n as isize + delta) as usize;
+            tokens.push(Token::new(token.kind, shift(token.start), shift(token.end)));
+        }
+    }
+
+    tokens.push(Token::new(
+        crate::token::TokenKind::Eof,
+        new_src.len(),
+        new_src.len(),
+    ));
+
+    let new_tree = ScopeTree::build(new_src, &tokens);
+
+    Reparsed {
+        tokens,
+        tree: new_tree,
+        dirty_start,
+        dirty_end,
+    }
+}
@@ -0,0 +1,543 @@
+//! Hand-rolled tokenizer for the subset of Rust syntax this crate highlights.
+//!
+//! The lexer is a single forward pass over the source text. It does not
+//! build an AST: it only needs enough local context (a delimiter stack, a
+//! handful of "are we inside X" flags) to classify each span correctly.
+//! Richer, mode-specific behaviour (macros, doc comments, lifetimes, ...)
+//! lives in sibling modules and is dispatched to from here.
+
+use crate::asm;
+use crate::doc;
+use crate::extern_ffi;
+use crate::lifetimes;
+use crate::macros::{self, MacroDefState};
+use crate::token::{Token, TokenKind};
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+// NB: "extern" is deliberately absent — it's classified as
+// `TokenKind::ExternKeyword` by `extern_ffi::lex_extern_head` before this
+// list is ever consulted.
+pub(crate) const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "type", "union",
+    "unsafe", "use", "where", "while",
+];
+
+/// Tokenize `src` into a flat, source-order list of [`Token`]s.
+///
+/// This is the crate's public tokenizing entry point; the lexing modules
+/// (macro handling, doc-comment recursion, etc.) are reached through it.
+pub fn tokenize(src: &str) -> Vec<Token> {
+    let mut lexer = Lexer::new(src);
+    lexer.run();
+    lexer.tokens
+}
+
+pub(crate) struct Lexer<'a> {
+    pub(crate) src: &'a str,
+    pub(crate) chars: Peekable<CharIndices<'a>>,
+    pub(crate) tokens: Vec<Token>,
+    /// Stack mirroring open `( [ {` delimiters. Each frame records whether
+    /// the bracket is part of an active `macro_rules!` matcher/transcriber,
+    /// so `$` retains its metavariable meaning at any nesting depth inside
+    /// that body and loses it as soon as the body's own delimiter closes.
+    pub(crate) macro_def_stack: Vec<MacroDefState>,
+    /// Mirrors `macro_def_stack` for `asm!`/`global_asm!` argument lists: a
+    /// frame is `true` while inside an active asm macro's delimiters, so
+    /// string literals and operand keywords get asm-specific treatment at
+    /// any nesting depth (e.g. inside a nested `options(...)`).
+    pub(crate) asm_stack: Vec<bool>,
+    /// Mirrors `macro_def_stack`/`asm_stack` for `extern "ABI" { ... }`
+    /// blocks: a frame is `true` while inside an active extern block, so the
+    /// name of the first `fn`/`static` item in each declaration gets tagged
+    /// [`TokenKind::ForeignItemName`] — everything directly inside an extern
+    /// block is a declaration, never a definition.
+    pub(crate) extern_stack: Vec<bool>,
+    /// Mirrors the other delimiter-tracking stacks for `$(...)` repetition
+    /// groups: a frame is `true` when its opening `(` was the one right
+    /// after a `$(` marker, so that once it closes, [`macros::lex_repetition_trailer`]
+    /// knows to classify the trailing separator/operator (`,` `*`, plain
+    /// `*`/`+`/`?`, ...) as [`TokenKind::MacroRepetition`] instead of
+    /// ordinary punctuation. Set for the *next* `push_delim` call via
+    /// [`Lexer::pending_repetition_open`], since the lexer only learns a
+    /// `(` belongs to a repetition group one token before it opens.
+    pub(crate) repetition_stack: Vec<bool>,
+    /// Set by [`macros::lex_metavariable`] right after it emits a `$(`
+    /// marker, consumed (and reset) by the very next `push_delim` call.
+    pub(crate) pending_repetition_open: bool,
+    /// Mirrors `asm_stack` for the argument list of a `clobber_abi(...)`
+    /// operand inside an `asm!`/`global_asm!` call: a frame is `true` while
+    /// inside it, so its string arguments (ABI names, not assembly text) are
+    /// tagged [`TokenKind::AbiString`] instead of being run through
+    /// [`asm::lex_template_string`]'s placeholder parsing. Set for the next
+    /// `push_delim` call via [`Lexer::pending_clobber_abi`].
+    pub(crate) clobber_abi_stack: Vec<bool>,
+    /// Set by [`Lexer::lex_ident_or_macro`] right after it emits a
+    /// `clobber_abi` operand keyword immediately followed by `(`, consumed
+    /// (and reset) by the very next `push_delim` call.
+    pub(crate) pending_clobber_abi: bool,
+    /// The last few non-trivial (code, not whitespace/comment) tokens
+    /// pushed, most recent first. [`lifetimes`] uses this to tell a
+    /// lifetime *declaration* (`fn longest<'a>`) from a lifetime *use*
+    /// (`Wrapper<'a>`) by looking at what introduced the enclosing `<`.
+    pub(crate) sig_hist: [Option<(TokenKind, usize, usize)>; 3],
+    /// `Some(true)`/`Some(false)` while inside a `<...>` that opened with a
+    /// lifetime (so it must be a generics list, see
+    /// [`lifetimes::maybe_open_angle`]), recording whether that list is a
+    /// declaration or a use; `None` outside of any such list. This is a
+    /// single flag rather than a full stack: lifetime generics nested inside
+    /// other lifetime generics replace the outer value, and any `>` resets it
+    /// to `None`, so the outer list's later lifetimes are left unclassified.
+    pub(crate) lifetime_angle_decl: Option<bool>,
+    /// Whether the most recently classified lifetime was a
+    /// [`TokenKind::LifetimeDecl`], so a following `:` can be recognized as
+    /// an outlives operator rather than ordinary punctuation.
+    pub(crate) last_lifetime_was_decl: bool,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(src: &'a str) -> Self {
+        Lexer {
+            src,
+            chars: src.char_indices().peekable(),
+            tokens: Vec::new(),
+            macro_def_stack: Vec::new(),
+            asm_stack: Vec::new(),
+            extern_stack: Vec::new(),
+            repetition_stack: Vec::new(),
+            pending_repetition_open: false,
+            clobber_abi_stack: Vec::new(),
+            pending_clobber_abi: false,
+            sig_hist: [None, None, None],
+            lifetime_angle_decl: None,
+            last_lifetime_was_decl: false,
+        }
+    }
+
+    fn run(&mut self) {
+        while let Some(&(start, c)) = self.chars.peek() {
+            if c.is_whitespace() {
+                self.consume_while(start, char::is_whitespace, TokenKind::Whitespace);
+            } else if c == '/' {
+                self.lex_slash(start);
+            } else if c == '"' && self.in_clobber_abi() {
+                extern_ffi::lex_abi_string(self);
+            } else if c == '"' && self.in_asm() {
+                asm::lex_template_string(self, start);
+            } else if c == '"' {
+                self.lex_string(start);
+            } else if c == '\'' {
+                self.lex_quote(start);
+            } else if c.is_ascii_digit() {
+                self.lex_number(start);
+            } else if c == '$' && self.in_macro_def() {
+                macros::lex_metavariable(self, start);
+            } else if is_ident_start(c) {
+                self.lex_ident_or_macro(start);
+            } else {
+                self.lex_punctuation(start, c);
+            }
+        }
+        let end = self.src.len();
+        self.tokens.push(Token::new(TokenKind::Eof, end, end));
+    }
+
+    pub(crate) fn in_macro_def(&self) -> bool {
+        matches!(self.macro_def_stack.last(), Some(MacroDefState::Active))
+    }
+
+    pub(crate) fn in_asm(&self) -> bool {
+        matches!(self.asm_stack.last(), Some(true))
+    }
+
+    pub(crate) fn in_extern_block(&self) -> bool {
+        matches!(self.extern_stack.last(), Some(true))
+    }
+
+    pub(crate) fn in_clobber_abi(&self) -> bool {
+        matches!(self.clobber_abi_stack.last(), Some(true))
+    }
+
+    /// Pushes a new delimiter frame. `macro_def`/`asm`/`extern_block` mark
+    /// this frame (and everything nested in it) as belonging to a
+    /// macro_rules body, an asm! argument list, or an extern block,
+    /// respectively; all three flags are inherited from the enclosing frame
+    /// so nested brackets stay in the same mode. Whether this frame is a
+    /// `$(...)` repetition group or a `clobber_abi(...)` argument list isn't
+    /// inherited (neither nests in any meaningful sense for our purposes) —
+    /// instead each is read from (and then cleared from) its own
+    /// `pending_*` flag, set by whichever caller just lexed the token
+    /// introducing this bracket.
+    pub(crate) fn push_delim(&mut self, macro_def: bool, asm: bool, extern_block: bool) {
+        let macro_state = if macro_def || self.in_macro_def() {
+            MacroDefState::Active
+        } else {
+            MacroDefState::Inactive
+        };
+        self.macro_def_stack.push(macro_state);
+        self.asm_stack.push(asm || self.in_asm());
+        self.extern_stack.push(extern_block || self.in_extern_block());
+        self.repetition_stack.push(self.pending_repetition_open);
+        self.pending_repetition_open = false;
+        self.clobber_abi_stack.push(self.pending_clobber_abi);
+        self.pending_clobber_abi = false;
+    }
+
+    /// Pops the innermost delimiter frame, returning whether it was a
+    /// `$(...)` repetition group (see [`Lexer::repetition_stack`]) so the
+    /// caller can classify the trailing separator/operator.
+    pub(crate) fn pop_delim(&mut self) -> bool {
+        self.macro_def_stack.pop();
+        self.asm_stack.pop();
+        self.extern_stack.pop();
+        self.clobber_abi_stack.pop();
+        self.repetition_stack.pop().unwrap_or(false)
+    }
+
+    pub(crate) fn bump(&mut self) -> Option<(usize, char)> {
+        self.chars.next()
+    }
+
+    pub(crate) fn peek_char(&mut self) -> Option<char> {
+        self.chars.peek().map(|&(_, c)| c)
+    }
+
+    pub(crate) fn push(&mut self, kind: TokenKind, start: usize, end: usize) {
+        self.tokens.push(Token::new(kind, start, end));
+        if !matches!(
+            kind,
+            TokenKind::Whitespace
+                | TokenKind::LineComment
+                | TokenKind::BlockComment
+                | TokenKind::DocCommentLine
+                | TokenKind::DocHeading
+                | TokenKind::DocCodeSpan
+                | TokenKind::DocFenceMarker
+        ) {
+            self.sig_hist.rotate_right(1);
+            self.sig_hist[0] = Some((kind, start, end));
+        }
+    }
+
+    /// Consumes and emits a single [`TokenKind::Whitespace`] token if the
+    /// next character is whitespace. No-op otherwise. Used by sub-lexers
+    /// (macros, asm, ...) that consume a fixed sequence of tokens manually
+    /// and need to account for the whitespace between them.
+    pub(crate) fn skip_whitespace(&mut self) {
+        if let Some(&(start, c)) = self.chars.peek() {
+            if c.is_whitespace() {
+                let mut end = start;
+                while let Some(&(i, c)) = self.chars.peek() {
+                    if !c.is_whitespace() {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    self.bump();
+                }
+                self.push(TokenKind::Whitespace, start, end);
+            }
+        }
+    }
+
+    fn consume_while(&mut self, start: usize, pred: impl Fn(char) -> bool, kind: TokenKind) {
+        let mut end = start;
+        while let Some(&(i, c)) = self.chars.peek() {
+            if !pred(c) {
+                break;
+            }
+            end = i + c.len_utf8();
+            self.chars.next();
+        }
+        self.push(kind, start, end);
+    }
+
+    fn lex_slash(&mut self, start: usize) {
+        if let Some(outer) = doc::line_doc_kind(self) {
+            doc::lex_doc_line_run(self, start, outer);
+            return;
+        }
+        if let Some(outer) = doc::block_doc_kind(self) {
+            doc::lex_doc_block(self, start, outer);
+            return;
+        }
+        self.bump();
+        match self.peek_char() {
+            Some('/') => {
+                self.consume_while(start, |c| c != '\n', TokenKind::LineComment);
+            }
+            Some('*') => {
+                self.bump();
+                let mut depth = 1usize;
+                let mut end = start + 2;
+                while let Some((i, c)) = self.bump() {
+                    end = i + c.len_utf8();
+                    if c == '/' && self.peek_char() == Some('*') {
+                        self.bump();
+                        end += 1;
+                        depth += 1;
+                    } else if c == '*' && self.peek_char() == Some('/') {
+                        self.bump();
+                        end += 1;
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                }
+                self.push(TokenKind::BlockComment, start, end);
+            }
+            _ => self.push(TokenKind::Punctuation, start, start + 1),
+        }
+    }
+
+    fn lex_string(&mut self, start: usize) {
+        self.bump();
+        let mut end = start + 1;
+        while let Some((i, c)) = self.bump() {
+            end = i + c.len_utf8();
+            if c == '\\' {
+                if let Some((ei, ec)) = self.bump() {
+                    end = ei + ec.len_utf8();
+                }
+                continue;
+            }
+            if c == '"' {
+                break;
+            }
+        }
+        self.push(TokenKind::StringLiteral, start, end);
+    }
+
+    /// A leading `'` starts either a char literal (`'a'`) or a lifetime
+    /// (`'a`). We disambiguate by scanning ahead: a lifetime is `'` followed
+    /// by an identifier that is *not* itself closed by another `'`.
+    fn lex_quote(&mut self, start: usize) {
+        let mut lookahead = self.chars.clone();
+        lookahead.next(); // the opening '
+        if let Some((_, c)) = lookahead.next() {
+            if is_ident_start(c) {
+                let mut after = lookahead.clone();
+                let mut ident_end_is_quote = false;
+                for (_, nc) in after.by_ref() {
+                    if nc == '\'' {
+                        ident_end_is_quote = true;
+                        break;
+                    }
+                    if !is_ident_continue(nc) {
+                        break;
+                    }
+                }
+                if !ident_end_is_quote {
+                    self.bump();
+                    let mut end = start + 1;
+                    while let Some(&(i, c)) = self.chars.peek() {
+                        if !is_ident_continue(c) {
+                            break;
+                        }
+                        end = i + c.len_utf8();
+                        self.chars.next();
+                    }
+                    lifetimes::lex_lifetime(self, start, end);
+                    return;
+                }
+            }
+        }
+        // Char literal: '<escaped-or-single-char>'
+        self.bump();
+        let mut end = start + 1;
+        if let Some((i, c)) = self.bump() {
+            end = i + c.len_utf8();
+            if c == '\\' {
+                if let Some((ei, ec)) = self.bump() {
+                    end = ei + ec.len_utf8();
+                }
+            }
+        }
+        if let Some((i, c)) = self.bump() {
+            if c == '\'' {
+                end = i + 1;
+            }
+        }
+        self.push(TokenKind::CharLiteral, start, end);
+    }
+
+    fn lex_number(&mut self, start: usize) {
+        let mut end = start;
+        let mut is_float = false;
+        while let Some(&(i, c)) = self.chars.peek() {
+            if c.is_ascii_digit() || c == '_' {
+                end = i + 1;
+                self.chars.next();
+            } else if c == '.' && !is_float {
+                is_float = true;
+                end = i + 1;
+                self.chars.next();
+            } else if is_ident_continue(c) {
+                // numeric suffix (u32, f64, ...)
+                end = i + c.len_utf8();
+                self.chars.next();
+            } else {
+                break;
+            }
+        }
+        let kind = if is_float {
+            TokenKind::FloatLiteral
+        } else {
+            TokenKind::IntLiteral
+        };
+        self.push(kind, start, end);
+    }
+
+    fn lex_ident_or_macro(&mut self, start: usize) {
+        let mut end = start;
+        while let Some(&(i, c)) = self.chars.peek() {
+            if !is_ident_continue(c) {
+                break;
+            }
+            end = i + c.len_utf8();
+            self.chars.next();
+        }
+        let name = &self.src[start..end];
+        if name == "macro_rules" && self.followed_by_bang() {
+            macros::lex_macro_rules_def(self, start, end);
+            return;
+        }
+        if (name == "asm" || name == "global_asm") && self.followed_by_bang_and_delim() {
+            asm::lex_asm_head(self, start, end);
+            return;
+        }
+        if name == "extern" {
+            extern_ffi::lex_extern_head(self, start, end);
+            return;
+        }
+        if self.followed_by_bang_and_delim() {
+            self.push(TokenKind::MacroName, start, end);
+            return;
+        }
+        let kind = if self.in_asm() && asm::OPERAND_KEYWORDS.contains(&name) {
+            TokenKind::AsmOperandKeyword
+        } else if self.in_extern_block()
+            && !KEYWORDS.contains(&name)
+            && extern_ffi::introduces_foreign_item_name(self)
+        {
+            TokenKind::ForeignItemName
+        } else if KEYWORDS.contains(&name) {
+            TokenKind::Keyword
+        } else {
+            TokenKind::Ident
+        };
+        self.push(kind, start, end);
+        if kind == TokenKind::AsmOperandKeyword && name == "clobber_abi" && self.followed_by_delim()
+        {
+            self.pending_clobber_abi = true;
+        }
+    }
+
+    /// Peeks past optional whitespace for one of the three bracket
+    /// delimiters, without consuming anything or requiring a `!` first
+    /// (unlike [`Lexer::followed_by_bang_and_delim`], which is specifically
+    /// for macro invocation heads).
+    fn followed_by_delim(&self) -> bool {
+        let mut it = self.chars.clone();
+        while let Some(&(_, c)) = it.peek() {
+            if c.is_whitespace() {
+                it.next();
+            } else {
+                break;
+            }
+        }
+        matches!(it.peek(), Some(&(_, '(' | '[' | '{')))
+    }
+
+    /// Peeks past optional whitespace for a `!`, without consuming anything.
+    pub(crate) fn followed_by_bang(&self) -> bool {
+        let mut it = self.chars.clone();
+        while let Some(&(_, c)) = it.peek() {
+            if c.is_whitespace() {
+                it.next();
+            } else {
+                break;
+            }
+        }
+        matches!(it.peek(), Some(&(_, '!')))
+    }
+
+    /// Peeks past `! <ws>*` for one of the three macro delimiters.
+    fn followed_by_bang_and_delim(&self) -> bool {
+        let mut it = self.chars.clone();
+        while let Some(&(_, c)) = it.peek() {
+            if c.is_whitespace() {
+                it.next();
+            } else {
+                break;
+            }
+        }
+        if !matches!(it.peek(), Some(&(_, '!'))) {
+            return false;
+        }
+        it.next();
+        while let Some(&(_, c)) = it.peek() {
+            if c.is_whitespace() {
+                it.next();
+            } else {
+                break;
+            }
+        }
+        matches!(it.peek(), Some(&(_, '(' | '[' | '{')))
+    }
+
+    fn lex_punctuation(&mut self, start: usize, c: char) {
+        if c == '<' {
+            lifetimes::maybe_open_angle(self);
+        } else if c == '>' {
+            lifetimes::maybe_close_angle(self);
+        }
+        if c == ':' && self.peek_is_outlives_colon() {
+            self.bump();
+            self.push(TokenKind::OutlivesOperator, start, start + 1);
+            return;
+        }
+        self.bump();
+        let closed_repetition_group = match c {
+            '(' | '[' | '{' => {
+                self.push_delim(false, false, false);
+                false
+            }
+            ')' | ']' | '}' => self.pop_delim(),
+            _ => false,
+        };
+        self.push(TokenKind::Punctuation, start, start + c.len_utf8());
+        if closed_repetition_group {
+            macros::lex_repetition_trailer(self);
+        }
+    }
+
+    /// A `:` is the outlives operator (`'b: 'a`) rather than ordinary
+    /// punctuation when the most recently lexed lifetime was a declaration
+    /// and the `:` is followed (past any whitespace) by another lifetime.
+    fn peek_is_outlives_colon(&self) -> bool {
+        if !self.last_lifetime_was_decl {
+            return false;
+        }
+        let mut it = self.chars.clone();
+        it.next(); // the ':' itself
+        while let Some(&(_, c)) = it.peek() {
+            if c.is_whitespace() {
+                it.next();
+            } else {
+                break;
+            }
+        }
+        matches!(it.peek(), Some(&(_, '\'')))
+    }
+}
+
+pub(crate) fn is_ident_start(c: char) -> bool {
+    c == '_' || c.is_alphabetic()
+}
+
+pub(crate) fn is_ident_continue(c: char) -> bool {
+    c == '_' || c.is_alphanumeric()
+}
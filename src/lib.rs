@@ -0,0 +1,20 @@
+//! rosettes: a Rust syntax highlighter.
+//!
+//! The crate is organized around a single-pass [`lexer::tokenize`] entry
+//! point that produces a flat list of [`token::Token`]s; feature-specific
+//! lexing (macros, and more as the highlighter grows) lives in its own
+//! module and is dispatched to from the lexer rather than bolted on as
+//! post-processing passes.
+
+mod asm;
+mod doc;
+mod extern_ffi;
+mod incremental;
+mod lexer;
+mod lifetimes;
+mod macros;
+mod token;
+
+pub use incremental::{reparse_edit, Edit, Reparsed, ScopeNode, ScopeTree};
+pub use lexer::tokenize;
+pub use token::{Token, TokenKind};
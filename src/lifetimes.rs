@@ -0,0 +1,93 @@
+//! Semantic classification of `'ident` lifetimes.
+//!
+//! `'a` means something different depending on where it sits: a
+//! *declaration* in a generic parameter list (`fn longest<'a>`), the
+//! right-hand side of an outlives *bound* (`'b: 'a`), a *use* at a
+//! reference or type-argument position (`&'a str`, `Wrapper<'a>`), or one
+//! of the reserved `'static`/`'_` lifetimes. Telling these apart needs a
+//! little context, tracked on [`Lexer`] itself rather than here:
+//!
+//! - [`Lexer::sig_hist`], the last few non-trivial tokens, to see what
+//!   introduced an enclosing `<` (`impl`, `for`, or a name right after
+//!   `fn`/`struct`/`enum`/`trait`/`union`/`type` means declaration,
+//!   anything else means use).
+//! - [`Lexer::lifetime_angle_decl`], set when a `<` opens with a lifetime
+//!   (see [`maybe_open_angle`]) and cleared at the next `>` — generics
+//!   lists always put lifetime params first, so "does `<` immediately
+//!   precede a lifetime" is an unambiguous signal that this bracket is a
+//!   generics list at all, with no risk of misreading a `<` comparison.
+//! - [`Lexer::last_lifetime_was_decl`], so the lexer's `:` handling can
+//!   recognize the outlives operator in `'b: 'a`.
+//!
+//! This is a single flag rather than a full bracket stack: a lifetime
+//! generics list nested inside another (e.g. the `Foo<'a>` in
+//! `struct S<'a, T: Foo<'a>, 'b>`) replaces the outer list's value with its
+//! own, and its `>` clears the flag, so lifetimes of the outer list that
+//! come after the nested list (the `'b` there) are classified as plain
+//! [`TokenKind::Lifetime`].
+
+use crate::lexer::Lexer;
+use crate::token::TokenKind;
+
+const DECL_INTRODUCERS: &[&str] = &["fn", "struct", "enum", "trait", "union", "type"];
+
+/// Classifies and pushes the lifetime token `src[start..end]` (including
+/// its leading `'`).
+pub(crate) fn lex_lifetime(lexer: &mut Lexer, start: usize, end: usize) {
+    let name = &lexer.src[start..end];
+    let kind = if name == "'static" || name == "'_" {
+        TokenKind::LifetimeStatic
+    } else if matches!(lexer.sig_hist[0], Some((TokenKind::Punctuation, s, e)) if &lexer.src[s..e] == "&")
+    {
+        TokenKind::LifetimeUse
+    } else if matches!(lexer.sig_hist[0], Some((TokenKind::Punctuation, s, e)) if &lexer.src[s..e] == "<" || &lexer.src[s..e] == ",")
+    {
+        match lexer.lifetime_angle_decl {
+            Some(true) => TokenKind::LifetimeDecl,
+            Some(false) => TokenKind::LifetimeUse,
+            None => TokenKind::Lifetime,
+        }
+    } else if matches!(lexer.sig_hist[0], Some((TokenKind::OutlivesOperator, _, _))) {
+        TokenKind::LifetimeBound
+    } else {
+        TokenKind::Lifetime
+    };
+
+    lexer.last_lifetime_was_decl = kind == TokenKind::LifetimeDecl;
+    lexer.push(kind, start, end);
+}
+
+/// Called when the lexer sees a `<`. If it's immediately followed (no
+/// whitespace permitted — lifetimes are always the first generic param)
+/// by a lifetime, this is unambiguously a generics list opening, and
+/// whether it's a declaration or a use is decided by what introduced it.
+pub(crate) fn maybe_open_angle(lexer: &mut Lexer) {
+    let mut it = lexer.chars.clone();
+    it.next(); // '<'
+    if !matches!(it.peek(), Some(&(_, '\''))) {
+        return;
+    }
+    lexer.lifetime_angle_decl = Some(introduced_by_decl_keyword(lexer));
+}
+
+/// Called when the lexer sees a `>`. Closes whatever lifetime-generics
+/// context is open, if any (see the nesting caveat on this module).
+pub(crate) fn maybe_close_angle(lexer: &mut Lexer) {
+    lexer.lifetime_angle_decl = None;
+}
+
+/// `fn longest<`, `struct Wrapper<`, `impl<`, and `for<` (HRTB) all
+/// introduce a declaration; anything else (a bare type name, `Self`, ...)
+/// is a reference to an already-declared lifetime.
+fn introduced_by_decl_keyword(lexer: &Lexer) -> bool {
+    match lexer.sig_hist[0] {
+        Some((TokenKind::Keyword, s, e)) => {
+            matches!(&lexer.src[s..e], "impl" | "for")
+        }
+        Some((TokenKind::Ident, ..)) => matches!(
+            lexer.sig_hist[1],
+            Some((TokenKind::Keyword, s, e)) if DECL_INTRODUCERS.contains(&&lexer.src[s..e])
+        ),
+        _ => false,
+    }
+}
@@ -0,0 +1,145 @@
+//! `macro_rules!` definitions and macro invocations.
+//!
+//! Two things make macros different from ordinary calls:
+//!
+//! 1. Delimiters must balance across the *whole* token tree a macro head
+//!    introduces ( `(...)`, `[...]`, or `{...}` ), and the body in between is
+//!    still ordinary Rust (plus, inside a definition, metavariables) that
+//!    should keep being highlighted rather than swallowed as one opaque
+//!    span. The lexer's delimiter stack in [`crate::lexer::Lexer`] already
+//!    does the balancing; this module only needs to flag which frames are
+//!    "inside an active macro_rules body" so `$` regains its meaning at any
+//!    depth.
+//! 2. A `macro_rules!` definition has its own sub-grammar: metavariable
+//!    binders (`$x:expr`), fragment specifiers (`expr`, `tt`, ...), and
+//!    repetition groups (`$(...)sep*`).
+
+use crate::lexer::{is_ident_continue, is_ident_start, Lexer};
+use crate::token::TokenKind;
+
+/// Whether an open delimiter frame is part of an active `macro_rules!`
+/// matcher/transcriber, i.e. whether `$` should be treated specially inside
+/// it (and inside anything nested within it).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MacroDefState {
+    Active,
+    Inactive,
+}
+
+const FRAGMENT_SPECIFIERS: &[&str] = &[
+    "block", "expr", "ident", "item", "lifetime", "literal", "meta", "pat", "pat_param", "path",
+    "stmt", "tt", "ty", "vis",
+];
+
+/// Called once the lexer has already identified `macro_rules` immediately
+/// followed by `!`: consumes `! <name> {`, pushing tokens for each piece,
+/// and marks the opened delimiter frame as an active macro definition.
+pub(crate) fn lex_macro_rules_def(lexer: &mut Lexer, start: usize, end: usize) {
+    lexer.push(TokenKind::MacroRulesKeyword, start, end);
+    lexer.skip_whitespace();
+    if let Some((i, '!')) = lexer.bump() {
+        lexer.push(TokenKind::Punctuation, i, i + 1);
+    }
+    lexer.skip_whitespace();
+
+    let name_start = lexer.chars.peek().map(|&(i, _)| i);
+    if let Some(name_start) = name_start {
+        let mut name_end = name_start;
+        while let Some(&(i, c)) = lexer.chars.peek() {
+            if !is_ident_continue(c) {
+                break;
+            }
+            name_end = i + c.len_utf8();
+            lexer.bump();
+        }
+        lexer.push(TokenKind::MacroName, name_start, name_end);
+    }
+    lexer.skip_whitespace();
+
+    if let Some((i, c @ ('(' | '[' | '{'))) = lexer.bump() {
+        lexer.push(TokenKind::Punctuation, i, i + 1);
+        let _ = c;
+        lexer.push_delim(true, false, false);
+    }
+}
+
+/// Called when the lexer sees `$` while inside an active macro_rules body.
+/// Handles two shapes: `$(` repetition groups and `$ident` metavariable
+/// references/binders (optionally followed by `:fragment`). The repetition
+/// operator that trails a closed `$(...)` is handled by
+/// [`lex_repetition_trailer`].
+pub(crate) fn lex_metavariable(lexer: &mut Lexer, start: usize) {
+    lexer.bump(); // '$'
+
+    if lexer.peek_char() == Some('(') {
+        // `$(` opens a repetition group; the `$` itself is the marker token,
+        // the `(` is tokenized normally (and balances like any other paren),
+        // but flagged so that once it closes, the trailing separator/operator
+        // (`,` `*`, bare `*`/`+`/`?`, ...) is recognized by
+        // `lex_repetition_trailer` below instead of falling out as ordinary
+        // punctuation.
+        lexer.push(TokenKind::MacroRepetition, start, start + 1);
+        lexer.pending_repetition_open = true;
+        return;
+    }
+
+    let name_start = start + 1;
+    let mut name_end = name_start;
+    while let Some(&(i, c)) = lexer.chars.peek() {
+        if !is_ident_continue(c) {
+            break;
+        }
+        name_end = i + c.len_utf8();
+        lexer.bump();
+    }
+    lexer.push(TokenKind::MacroMetavariable, start, name_end);
+
+    if lexer.peek_char() == Some(':') {
+        let mut ahead = lexer.chars.clone();
+        let (colon_idx, _) = ahead.next().unwrap();
+        if let Some(&(spec_start, c)) = ahead.peek() {
+            if is_ident_start(c) {
+                lexer.bump(); // ':'
+                lexer.push(TokenKind::Punctuation, colon_idx, colon_idx + 1);
+                let mut spec_end = spec_start;
+                while let Some(&(i, c)) = lexer.chars.peek() {
+                    if !is_ident_continue(c) {
+                        break;
+                    }
+                    spec_end = i + c.len_utf8();
+                    lexer.bump();
+                }
+                let kind = if FRAGMENT_SPECIFIERS.contains(&&lexer.src[spec_start..spec_end]) {
+                    TokenKind::MacroFragmentSpecifier
+                } else {
+                    TokenKind::Ident
+                };
+                lexer.push(kind, spec_start, spec_end);
+            }
+        }
+    }
+}
+
+/// Called right after a `$(...)` repetition group's closing `)` has been
+/// lexed (see [`Lexer::pending_repetition_open`]/`repetition_stack`).
+/// Classifies what comes next: a bare `*`/`+`/`?` operator, or a single
+/// separator token (almost always `,` or `;`) followed by the operator.
+pub(crate) fn lex_repetition_trailer(lexer: &mut Lexer) {
+    lexer.skip_whitespace();
+    match lexer.peek_char() {
+        Some('*' | '+' | '?') => {
+            let (i, c) = lexer.bump().unwrap();
+            lexer.push(TokenKind::MacroRepetition, i, i + c.len_utf8());
+        }
+        Some(c) if !c.is_whitespace() => {
+            let (i, sep) = lexer.bump().unwrap();
+            lexer.push(TokenKind::MacroRepetition, i, i + sep.len_utf8());
+            lexer.skip_whitespace();
+            if let Some(op @ ('*' | '+' | '?')) = lexer.peek_char() {
+                let (oi, _) = lexer.bump().unwrap();
+                lexer.push(TokenKind::MacroRepetition, oi, oi + op.len_utf8());
+            }
+        }
+        _ => {}
+    }
+}
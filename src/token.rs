@@ -0,0 +1,117 @@
+//! Token kinds produced by the Rust highlighter.
+
+/// Classification assigned to a single lexical span of source text.
+///
+/// Variants are deliberately fine-grained: callers that only care about a
+/// handful of buckets (comment, string, keyword, ...) can match with a
+/// wildcard arm, while callers that want richer styling (e.g. distinguishing
+/// a lifetime declaration from a lifetime use) can match exhaustively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Whitespace,
+    LineComment,
+    BlockComment,
+    Ident,
+    Keyword,
+    /// A lifetime whose role the lexer couldn't pin down (e.g. a loop
+    /// label, which shares `'ident` syntax with lifetimes but isn't one).
+    Lifetime,
+    /// A lifetime parameter being introduced in a generics list, e.g. `'a`
+    /// in `fn longest<'a, 'b: 'a>` (including the `'b` there, even though
+    /// it also carries a bound).
+    LifetimeDecl,
+    /// The right-hand side of an outlives bound, e.g. `'a` in `'b: 'a`.
+    LifetimeBound,
+    /// A lifetime used at a reference or type-argument position, e.g. `'a`
+    /// in `&'a str` or in `Wrapper<'a>`.
+    LifetimeUse,
+    /// The reserved `'static` or `'_` lifetime.
+    LifetimeStatic,
+    /// The `:` in an outlives bound (`'b: 'a`), as opposed to an ordinary
+    /// punctuation `:`.
+    OutlivesOperator,
+    IntLiteral,
+    FloatLiteral,
+    StringLiteral,
+    CharLiteral,
+    ByteStringLiteral,
+    Punctuation,
+    /// `name` in `name!(...)`, `name![...]`, or `name! { ... }`.
+    MacroName,
+    /// The `macro_rules` keyword itself (not a generic `Keyword`, since it's
+    /// contextual and only meaningful immediately before a `!`).
+    MacroRulesKeyword,
+    /// A metavariable binder or reference, e.g. `$x` in `$x:expr` or in the
+    /// expansion body.
+    MacroMetavariable,
+    /// The fragment specifier following a metavariable binder, e.g. `expr`
+    /// in `$x:expr` (the token covers just the specifier, not the `:`).
+    MacroFragmentSpecifier,
+    /// A repetition operator in a matcher or transcriber: `*`, `+`, `?`
+    /// immediately following a `$(...)` group, the separator token that
+    /// precedes it, or the `$` that opens the group.
+    MacroRepetition,
+    /// The literal (non-placeholder) text inside an `asm!`/`global_asm!`
+    /// template string. Kept distinct from [`StringLiteral`](Self::StringLiteral)
+    /// since it is assembly text, not a Rust string, even though it is
+    /// written with the same quoting.
+    AsmTemplateString,
+    /// A `{0}`, `{name}`, or `{:e}` format placeholder inside an asm
+    /// template string.
+    AsmPlaceholder,
+    /// An operand-direction keyword (`in`, `out`, `inout`, `lateout`,
+    /// `const`, `sym`) or the `options`/`clobber_abi` flag introducers,
+    /// recognized only inside an `asm!`/`global_asm!` argument list.
+    AsmOperandKeyword,
+    /// The `///`, `//!`, `/**`, or `/*!` marker (and closing `*/` for the
+    /// block forms) introducing a doc comment, plus any of its body text
+    /// that isn't a heading, code span, fence line, or Rust code block (the
+    /// contents of a non-Rust fence are tagged with this kind too).
+    DocCommentLine,
+    /// A Markdown-lite `# Heading` line inside a doc comment.
+    DocHeading,
+    /// An inline `` `code` `` span inside doc comment prose.
+    DocCodeSpan,
+    /// A fence delimiter line (the ` ``` ` or ` ```rust ` line) around a
+    /// doctest inside a doc comment.
+    DocFenceMarker,
+    /// The `extern` keyword, in either of its two positions: introducing an
+    /// FFI block (`extern "C" { ... }`) or qualifying a function's calling
+    /// convention (`extern "C" fn foo() { ... }`).
+    ExternKeyword,
+    /// A string naming a calling convention: `"C"` in `extern "C"`, or an
+    /// argument to `clobber_abi(...)` inside `asm!`/`global_asm!`. Kept
+    /// distinct from [`StringLiteral`](Self::StringLiteral) since it names a
+    /// fixed ABI, not arbitrary string data (and, inside `asm!`, from
+    /// [`AsmTemplateString`](Self::AsmTemplateString) since it isn't
+    /// assembly text either).
+    AbiString,
+    /// The name of an `fn` or `static` item declared directly inside an
+    /// `extern` block. Such items are declarations, not definitions (they
+    /// have no body/initializer and end in `;`), which is worth flagging
+    /// separately from an ordinary item name.
+    ForeignItemName,
+    Eof,
+}
+
+/// A classified span of source text.
+///
+/// `start`/`end` are byte offsets into the original source string, so spans
+/// can be sliced back out with `&src[token.start..token.end]` without any
+/// additional bookkeeping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Token {
+    pub fn new(kind: TokenKind, start: usize, end: usize) -> Self {
+        Token { kind, start, end }
+    }
+
+    pub fn text<'a>(&self, src: &'a str) -> &'a str {
+        &src[self.start..self.end]
+    }
+}
@@ -0,0 +1,54 @@
+use std::arch::asm;
+
+fn add_one(x: u64) -> u64 {
+    let result: u64;
+    unsafe {
+        asm!(
+            "mov {tmp}, {x}",
+            "add {tmp}, 1",
+            "mov {result}, {tmp}",
+            x = in(reg) x,
+            tmp = out(reg) _,
+            result = out(reg) result,
+        );
+    }
+    result
+}
+
+fn named_and_format_spec(x: u32) -> u32 {
+    let result: u32;
+    unsafe {
+        asm!(
+            "mov {0:e}, {1:e}",
+            "add {0:e}, {2}",
+            out(reg) result,
+            in(reg) x,
+            const 1,
+            options(nostack, nomem),
+        );
+    }
+    result
+}
+
+fn concatenated_templates(x: u64, y: u64) -> u64 {
+    let result: u64;
+    unsafe {
+        asm!(
+            "mov {tmp}, {x}" ,
+            "add {tmp}, {y}",
+            "mov {result}, {{tmp}}",
+            x = in(reg) x,
+            y = in(reg) y,
+            tmp = lateout(reg) _,
+            result = out(reg) result,
+            clobber_abi("C"),
+        );
+    }
+    result
+}
+
+std::arch::global_asm!(
+    ".global my_asm_func",
+    "my_asm_func:",
+    "ret",
+);
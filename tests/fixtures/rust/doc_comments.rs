@@ -0,0 +1,49 @@
+/// Adds one to the given number.
+///
+/// # Examples
+///
+/// ```
+/// let five = 5;
+/// assert_eq!(six(five), 6);
+/// ```
+///
+/// A non-rust fence is left as plain text:
+///
+/// ```text
+/// this is not highlighted
+/// ```
+///
+/// An ignored/no_run example is still Rust, just not executed as a test:
+///
+/// ```ignore
+/// six(panic!());
+/// ```
+///
+/// An indented example, the older doctest style:
+///
+///     let seven = six(6);
+///
+fn six(x: i32) -> i32 {
+    x + 1
+}
+
+/**
+ * A block doc comment with its own fenced example.
+ *
+ * ```rust,no_run
+ * let _ = Widget::new();
+ * ```
+ */
+struct Widget;
+
+impl Widget {
+    fn new() -> Self {
+        Self
+    }
+}
+
+//! Crate-level docs can also contain doctests.
+//!
+//! ```
+//! assert!(true);
+//! ```
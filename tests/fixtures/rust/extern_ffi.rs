@@ -0,0 +1,21 @@
+extern "C" {
+    fn abs(input: i32) -> i32;
+
+    static GLOBAL_COUNTER: u32;
+
+    fn printf(format: *const u8, ...) -> i32;
+}
+
+unsafe extern "C" {
+    fn unsafe_variant(value: i32) -> i32;
+}
+
+#[no_mangle]
+#[repr(C)]
+pub extern "C" fn exported(x: i32, y: i32) -> i32 {
+    x + y
+}
+
+extern "system" {
+    fn GetLastError() -> u32;
+}
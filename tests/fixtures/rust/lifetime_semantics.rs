@@ -0,0 +1,22 @@
+fn elided(s: &str) -> &'_ str {
+    s
+}
+
+trait Callback<'a> {
+    fn call(&self, s: &'a str);
+}
+
+fn apply<F>(f: F)
+where
+    F: for<'a> Fn(&'a str),
+{
+    f("hi");
+}
+
+fn labeled_loop() {
+    'outer: loop {
+        loop {
+            break 'outer;
+        }
+    }
+}
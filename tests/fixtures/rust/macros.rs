@@ -0,0 +1,27 @@
+macro_rules! my_vec {
+    () => {
+        Vec::new()
+    };
+    ($($x:expr),* $(,)?) => {
+        {
+            let mut v = Vec::new();
+            $(v.push($x);)*
+            v
+        }
+    };
+}
+
+macro_rules! log_with_crate {
+    ($($arg:tt)*) => {
+        $crate::log(format!($($arg)*))
+    };
+}
+
+fn main() {
+    let v = my_vec![1, 2, 3];
+    println!("{:?}", v);
+
+    let nested = vec![println!("inside"), 1];
+
+    let braced = my_vec! { 1, 2 };
+}